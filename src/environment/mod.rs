@@ -38,7 +38,16 @@ pub trait Environment: 'static + Clone + Debug + Default + Send + Sync {
     /// The specified type of node.
     const NODE_TYPE: NodeType;
     /// The version of the network protocol; it can be incremented in order to force users to update.
-    const MESSAGE_VERSION: u32 = 5;
+    const MESSAGE_VERSION: u32 = 6;
+
+    /// Marks peer connections as using the RLPx-style transport in `peers::transport`, built on
+    /// `GroupEncryption`: it protects gossip from passive eavesdropping, and every frame is
+    /// MAC-authenticated once the connection is established, protecting it from on-path
+    /// tampering in transit. The ephemeral handshake itself is not bound to a static node
+    /// identity, so it does not defend against an active attacker substituting keys during
+    /// connection setup. The transport module does not read this flag, and its handshake does
+    /// not exchange the flag between peers.
+    const ENCRYPTED_TRANSPORT: bool = true;
 
     /// If `true`, a mining node will craft public coinbase transactions.
     const COINBASE_IS_PUBLIC: bool = false;
@@ -67,11 +76,27 @@ pub trait Environment: 'static + Clone + Debug + Default + Send + Sync {
     const RADIO_SILENCE_IN_SECS: u64 = 120; // 2 minutes
     /// The duration in seconds after which to expire a failure from a peer.
     const FAILURE_EXPIRY_TIME_IN_SECS: u64 = 7200; // 2 hours
+    /// The duration in seconds that a banned peer is refused connections for, before the ban
+    /// automatically lifts.
+    const BAN_WINDOW_IN_SECS: u64 = 86400; // 24 hours
 
     /// The minimum number of peers required to maintain connections with.
     const MINIMUM_NUMBER_OF_PEERS: usize;
     /// The maximum number of peers permitted to maintain connections with.
     const MAXIMUM_NUMBER_OF_PEERS: usize = 21;
+    /// The number of outbound connections the node actively dials candidate peers to maintain,
+    /// guaranteeing outbound diversity regardless of how many inbound peers have connected.
+    const PREFERRED_OUTBOUND_PEERS: usize = Self::MINIMUM_NUMBER_OF_PEERS;
+    /// The maximum number of outbound connections permitted, enforced independently of
+    /// `MAXIMUM_INBOUND_PEERS`. Defaults to `PREFERRED_OUTBOUND_PEERS`, the number of outbound
+    /// slots the node actually tries to fill.
+    const MAXIMUM_OUTBOUND_PEERS: usize = Self::PREFERRED_OUTBOUND_PEERS;
+    /// The maximum number of inbound connections permitted, enforced independently of
+    /// `MAXIMUM_OUTBOUND_PEERS` so inbound sockets cannot monopolize the peer table. Defaults to
+    /// whatever remains of `MAXIMUM_NUMBER_OF_PEERS` after reserving `MAXIMUM_OUTBOUND_PEERS`
+    /// slots for outbound connections, so the two caps sum to `MAXIMUM_NUMBER_OF_PEERS` and the
+    /// aggregate connection budget it promises is preserved.
+    const MAXIMUM_INBOUND_PEERS: usize = Self::MAXIMUM_NUMBER_OF_PEERS - Self::MAXIMUM_OUTBOUND_PEERS;
     /// The maximum number of connection failures permitted by an inbound connecting peer.
     const MAXIMUM_CONNECTION_FAILURES: u32 = 5;
     /// The maximum number of candidate peers permitted to be stored in the node.
@@ -83,6 +108,17 @@ pub trait Environment: 'static + Clone + Debug + Default + Send + Sync {
     const MAXIMUM_BLOCK_REQUEST: u32 = 100;
     /// The maximum number of failures tolerated before disconnecting from a peer.
     const MAXIMUM_NUMBER_OF_FAILURES: usize = 2400;
+
+    /// If `true`, the node will only connect to peers in its preferred/allow list,
+    /// instead of dialing out to the wider network.
+    const RESERVED_ONLY: bool = false;
+
+    /// The number of worker threads across which block download and verification is spread
+    /// during syncing.
+    const NUMBER_OF_SYNC_THREADS: usize = 4;
+    /// The maximum number of half-open connections - handshakes that have started but not yet
+    /// completed - the node tolerates at once.
+    const MAXIMUM_PENDING_PEERS: usize = 100;
 }
 
 #[derive(Clone, Debug, Default)]
@@ -115,6 +151,7 @@ impl<N: Network> Environment for SyncNode<N> {
     const NODE_TYPE: NodeType = NodeType::Sync;
     const MINIMUM_NUMBER_OF_PEERS: usize = 5;
     const MAXIMUM_NUMBER_OF_PEERS: usize = 1024;
+    const MAXIMUM_PENDING_PEERS: usize = 2048;
 }
 
 #[derive(Clone, Debug, Default)]
@@ -0,0 +1,222 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    collections::HashSet,
+    fmt,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
+
+/// An IPv4/IPv6 address range expressed in CIDR notation, e.g. `10.0.0.0/8`.
+/// A bare IP address is treated as a `/32` (or `/128`) range.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct IpRange {
+    address: IpAddr,
+    prefix_len: u32,
+}
+
+impl IpRange {
+    /// Returns `true` if `ip` falls within this range.
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.address, ip) {
+            (IpAddr::V4(range), IpAddr::V4(candidate)) => {
+                let mask = if self.prefix_len == 0 { 0 } else { u32::MAX << (32 - self.prefix_len) };
+                (u32::from(range) & mask) == (u32::from(*candidate) & mask)
+            }
+            (IpAddr::V6(range), IpAddr::V6(candidate)) => {
+                let mask = if self.prefix_len == 0 { 0 } else { u128::MAX << (128 - self.prefix_len) };
+                (u128::from(range) & mask) == (u128::from(*candidate) & mask)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = PeerAccessControlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('/') {
+            Some((address, prefix_len)) => {
+                let address =
+                    IpAddr::from_str(address).map_err(|_| PeerAccessControlError::InvalidEntry(s.to_string()))?;
+                let prefix_len = prefix_len
+                    .parse::<u32>()
+                    .map_err(|_| PeerAccessControlError::InvalidEntry(s.to_string()))?;
+                let max_prefix_len = if address.is_ipv4() { 32 } else { 128 };
+                if prefix_len > max_prefix_len {
+                    return Err(PeerAccessControlError::InvalidEntry(s.to_string()));
+                }
+                Ok(Self { address, prefix_len })
+            }
+            None => {
+                // Also accept a `host:port` socket address, keeping only the IP.
+                let address = if let Ok(socket_addr) = SocketAddr::from_str(s) {
+                    socket_addr.ip()
+                } else {
+                    IpAddr::from_str(s).map_err(|_| PeerAccessControlError::InvalidEntry(s.to_string()))?
+                };
+                let prefix_len = if address.is_ipv4() { 32 } else { 128 };
+                Ok(Self { address, prefix_len })
+            }
+        }
+    }
+}
+
+/// The set of reasons a peer access list may fail to parse.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PeerAccessControlError {
+    /// The given entry is neither a valid CIDR range, IP address, nor socket address.
+    InvalidEntry(String),
+}
+
+impl fmt::Display for PeerAccessControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidEntry(entry) => write!(f, "invalid peer access list entry: \"{}\"", entry),
+        }
+    }
+}
+
+impl std::error::Error for PeerAccessControlError {}
+
+/// Consults operator-configured allow, deny, and preferred peer lists before a connection
+/// is accepted or dialed, so private or permissioned clusters can restrict membership beyond
+/// the hardcoded `SYNC_NODES`.
+#[derive(Clone, Debug, Default)]
+pub struct PeerAccessControl {
+    /// If non-empty, only peers matching one of these ranges - or the `preferred` list - may
+    /// connect.
+    allow: HashSet<IpRange>,
+    /// Peers matching one of these ranges are always refused, even if also allowed or preferred.
+    deny: HashSet<IpRange>,
+    /// Peers that are actively re-dialed until `MINIMUM_NUMBER_OF_PEERS` is met. Stored with the
+    /// exact `SocketAddr` to dial, since that is the address this list is used to redial.
+    preferred: HashSet<SocketAddr>,
+    /// If `true`, only "reserved" peers - those on the `allow` or `preferred` lists - are
+    /// permitted to connect.
+    reserved_only: bool,
+}
+
+impl PeerAccessControl {
+    /// Parses the given `peers_allow`, `peers_deny`, and `peers_preferred` entries into a new
+    /// `PeerAccessControl`. Allow/deny entries may be CIDR ranges, bare IPs, or `SocketAddr`
+    /// strings; preferred entries must be `SocketAddr` strings.
+    pub fn new(
+        peers_allow: &[String],
+        peers_deny: &[String],
+        peers_preferred: &[String],
+        reserved_only: bool,
+    ) -> Result<Self, PeerAccessControlError> {
+        let allow = peers_allow.iter().map(|entry| IpRange::from_str(entry)).collect::<Result<_, _>>()?;
+        let deny = peers_deny.iter().map(|entry| IpRange::from_str(entry)).collect::<Result<_, _>>()?;
+        let preferred = peers_preferred
+            .iter()
+            .map(|entry| SocketAddr::from_str(entry).map_err(|_| PeerAccessControlError::InvalidEntry(entry.clone())))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { allow, deny, preferred, reserved_only })
+    }
+
+    /// Returns `true` if a connection - inbound or outbound - to/from `addr` should be permitted.
+    ///
+    /// `preferred` is matched by IP alone here, not the full `SocketAddr` (including port)
+    /// used when dialing: an inbound connection from a preferred peer arrives from an ephemeral
+    /// source port, which would never match the configured dial address.
+    pub fn is_connection_allowed(&self, addr: &SocketAddr) -> bool {
+        if self.deny.iter().any(|range| range.contains(&addr.ip())) {
+            return false;
+        }
+
+        let is_reserved = self.is_preferred_ip(&addr.ip()) || self.allow.iter().any(|range| range.contains(&addr.ip()));
+
+        if self.reserved_only {
+            return is_reserved;
+        }
+
+        self.allow.is_empty() || is_reserved
+    }
+
+    /// Returns `true` if `addr` is one of the preferred peers that should be actively re-dialed.
+    pub fn is_preferred(&self, addr: &SocketAddr) -> bool {
+        self.preferred.contains(addr)
+    }
+
+    /// Returns `true` if `ip` matches one of the preferred peers' addresses, ignoring port.
+    fn is_preferred_ip(&self, ip: &IpAddr) -> bool {
+        self.preferred.iter().any(|preferred| &preferred.ip() == ip)
+    }
+
+    /// Returns the configured preferred peers.
+    pub fn preferred_peers(&self) -> impl Iterator<Item = &SocketAddr> {
+        self.preferred.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cidr_range_contains_expected_addresses() {
+        let range = IpRange::from_str("10.0.0.0/8").unwrap();
+        assert!(range.contains(&"10.1.2.3".parse().unwrap()));
+        assert!(!range.contains(&"11.1.2.3".parse().unwrap()));
+    }
+
+    #[test]
+    fn bare_ip_is_treated_as_single_host() {
+        let range = IpRange::from_str("127.0.0.1").unwrap();
+        assert!(range.contains(&"127.0.0.1".parse().unwrap()));
+        assert!(!range.contains(&"127.0.0.2".parse().unwrap()));
+    }
+
+    #[test]
+    fn deny_list_overrides_allow_list() {
+        let access = PeerAccessControl::new(
+            &["10.0.0.0/8".to_string()],
+            &["10.0.0.1/32".to_string()],
+            &[],
+            false,
+        )
+        .unwrap();
+
+        assert!(access.is_connection_allowed(&"10.0.0.2:4130".parse().unwrap()));
+        assert!(!access.is_connection_allowed(&"10.0.0.1:4130".parse().unwrap()));
+        assert!(!access.is_connection_allowed(&"8.8.8.8:4130".parse().unwrap()));
+    }
+
+    #[test]
+    fn reserved_only_permits_preferred_peers_regardless_of_source_port() {
+        let preferred: SocketAddr = "127.0.0.1:4132".parse().unwrap();
+        let access = PeerAccessControl::new(&[], &[], &[preferred.to_string()], true).unwrap();
+
+        // An inbound connection from a preferred peer arrives on an ephemeral source port, not
+        // the port it is dialed on - it must still be recognized as reserved.
+        assert!(access.is_connection_allowed(&"127.0.0.1:55555".parse().unwrap()));
+        assert!(!access.is_connection_allowed(&"10.0.0.5:4132".parse().unwrap()));
+    }
+
+    #[test]
+    fn reserved_only_permits_allow_listed_peers() {
+        let access =
+            PeerAccessControl::new(&["10.0.0.0/8".to_string()], &[], &[], true).unwrap();
+
+        assert!(access.is_connection_allowed(&"10.1.2.3:4132".parse().unwrap()));
+        assert!(!access.is_connection_allowed(&"8.8.8.8:4132".parse().unwrap()));
+    }
+}
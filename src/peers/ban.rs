@@ -0,0 +1,137 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::environment::Environment;
+
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+/// Tracks connection failures per peer IP and, once a peer exceeds
+/// `MAXIMUM_NUMBER_OF_FAILURES` or is explicitly reported for sending an invalid block or
+/// message, bans it for `BAN_WINDOW_IN_SECS` before automatically lifting the ban.
+#[derive(Debug)]
+pub struct PeerBanList<E: Environment> {
+    /// The number of outstanding failures recorded for each peer.
+    failures: HashMap<IpAddr, usize>,
+    /// The instant at which each currently-banned peer was banned.
+    bans: HashMap<IpAddr, Instant>,
+    _environment: PhantomData<E>,
+}
+
+impl<E: Environment> Default for PeerBanList<E> {
+    fn default() -> Self {
+        Self { failures: Default::default(), bans: Default::default(), _environment: PhantomData }
+    }
+}
+
+impl<E: Environment> PeerBanList<E> {
+    /// Creates a new, empty `PeerBanList`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a connection failure for `ip`, banning it once `MAXIMUM_NUMBER_OF_FAILURES`
+    /// is exceeded. Returns `true` if `ip` is now banned.
+    pub fn record_failure(&mut self, ip: IpAddr) -> bool {
+        let failures = self.failures.entry(ip).or_insert(0);
+        *failures += 1;
+
+        if *failures > E::MAXIMUM_NUMBER_OF_FAILURES {
+            self.ban(ip);
+            return true;
+        }
+
+        false
+    }
+
+    /// Immediately bans `ip` for `BAN_WINDOW_IN_SECS`, e.g. after it sends an invalid block
+    /// or message.
+    pub fn ban(&mut self, ip: IpAddr) {
+        self.bans.insert(ip, Instant::now());
+    }
+
+    /// Returns `true` if `ip` is currently banned, automatically lifting the ban - and clearing
+    /// its recorded failures - if `BAN_WINDOW_IN_SECS` has elapsed.
+    pub fn is_banned(&mut self, ip: &IpAddr) -> bool {
+        let Some(banned_at) = self.bans.get(ip) else {
+            return false;
+        };
+
+        if banned_at.elapsed() >= Duration::from_secs(E::BAN_WINDOW_IN_SECS) {
+            self.unban(ip);
+            return false;
+        }
+
+        true
+    }
+
+    /// Clears the ban, if any, and the recorded failures for `ip`.
+    pub fn unban(&mut self, ip: &IpAddr) {
+        self.bans.remove(ip);
+        self.failures.remove(ip);
+    }
+
+    /// Returns the set of currently banned peer IPs, along with how long each has been banned.
+    pub fn banned_peers(&self) -> impl Iterator<Item = (&IpAddr, Duration)> {
+        self.bans.iter().map(|(ip, banned_at)| (ip, banned_at.elapsed()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::NodeType;
+    use snarkvm::dpc::testnet2::Testnet2;
+
+    #[derive(Clone, Debug, Default)]
+    struct TestEnvironment;
+
+    #[rustfmt::skip]
+    impl Environment for TestEnvironment {
+        type Network = Testnet2;
+        const NODE_TYPE: NodeType = NodeType::Client;
+        const MINIMUM_NUMBER_OF_PEERS: usize = 2;
+        const MAXIMUM_NUMBER_OF_FAILURES: usize = 2;
+        const BAN_WINDOW_IN_SECS: u64 = 60;
+    }
+
+    #[test]
+    fn peer_is_banned_after_exceeding_failure_threshold() {
+        let mut bans = PeerBanList::<TestEnvironment>::new();
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+
+        assert!(!bans.record_failure(ip));
+        assert!(!bans.record_failure(ip));
+        assert!(bans.record_failure(ip));
+        assert!(bans.is_banned(&ip));
+    }
+
+    #[test]
+    fn explicit_ban_can_be_cleared() {
+        let mut bans = PeerBanList::<TestEnvironment>::new();
+        let ip: IpAddr = "127.0.0.2".parse().unwrap();
+
+        bans.ban(ip);
+        assert!(bans.is_banned(&ip));
+
+        bans.unban(&ip);
+        assert!(!bans.is_banned(&ip));
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    environment::Environment,
+    peers::{PeerAccessControl, PeerBanList},
+};
+
+use std::{
+    collections::{HashSet, VecDeque},
+    marker::PhantomData,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+/// Tracks inbound and outbound connection counts independently, so an operator's
+/// `MAXIMUM_INBOUND_PEERS` and `MAXIMUM_OUTBOUND_PEERS` are enforced separately and the node
+/// keeps dialing candidates until `PREFERRED_OUTBOUND_PEERS` is reached. This prevents
+/// eclipse-style situations where an attacker monopolizes the peer table with inbound sockets.
+#[derive(Debug)]
+pub struct ConnectionManager<E: Environment> {
+    access_control: PeerAccessControl,
+    bans: PeerBanList<E>,
+    inbound: HashSet<SocketAddr>,
+    outbound: HashSet<SocketAddr>,
+    /// Connections for which a handshake has started but not yet completed, in the order they
+    /// were started.
+    pending: VecDeque<(SocketAddr, Instant)>,
+    _environment: PhantomData<E>,
+}
+
+impl<E: Environment> ConnectionManager<E> {
+    /// Creates a new `ConnectionManager` that consults `access_control` before admitting peers.
+    pub fn new(access_control: PeerAccessControl) -> Self {
+        Self {
+            access_control,
+            bans: PeerBanList::new(),
+            inbound: Default::default(),
+            outbound: Default::default(),
+            pending: Default::default(),
+            _environment: PhantomData,
+        }
+    }
+
+    /// Begins tracking a handshake with `addr`, dropping the oldest pending handshake if the
+    /// node is already at `MAXIMUM_PENDING_PEERS`, so a flood of half-open connections cannot
+    /// grow the pending set without bound. Returns `false` if `addr` already has a handshake
+    /// in progress.
+    pub fn begin_handshake(&mut self, addr: SocketAddr) -> bool {
+        self.expire_pending();
+
+        if self.pending.iter().any(|(pending_addr, _)| pending_addr == &addr) {
+            return false;
+        }
+
+        if self.pending.len() >= E::MAXIMUM_PENDING_PEERS {
+            self.pending.pop_front();
+        }
+        self.pending.push_back((addr, Instant::now()));
+
+        true
+    }
+
+    /// Marks the handshake with `addr` as complete, removing it from the pending set.
+    pub fn complete_handshake(&mut self, addr: &SocketAddr) {
+        self.pending.retain(|(pending_addr, _)| pending_addr != addr);
+    }
+
+    /// Drops any pending handshake that has been outstanding for longer than
+    /// `CONNECTION_TIMEOUT_IN_SECS`.
+    pub fn expire_pending(&mut self) {
+        let timeout = Duration::from_secs(E::CONNECTION_TIMEOUT_IN_SECS);
+        self.pending.retain(|(_, started_at)| started_at.elapsed() < timeout);
+    }
+
+    /// Returns the number of handshakes currently in progress.
+    pub fn num_pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns the total number of currently connected peers, inbound and outbound combined.
+    pub fn num_connected(&self) -> usize {
+        self.inbound.len() + self.outbound.len()
+    }
+
+    /// Returns `true` if an inbound connection from `addr` should be accepted.
+    ///
+    /// In addition to the directional `MAXIMUM_INBOUND_PEERS` cap, this also enforces the
+    /// aggregate `MAXIMUM_NUMBER_OF_PEERS` budget, so a node cannot end up sustaining more
+    /// connections in total than that constant promises even if the directional caps are
+    /// overridden without being kept in sync.
+    pub fn should_accept_inbound(&mut self, addr: &SocketAddr) -> bool {
+        self.inbound.len() < E::MAXIMUM_INBOUND_PEERS
+            && self.num_connected() < E::MAXIMUM_NUMBER_OF_PEERS
+            && !self.bans.is_banned(&addr.ip())
+            && self.access_control.is_connection_allowed(addr)
+    }
+
+    /// Returns `true` if the node should dial `addr` as an outbound connection.
+    ///
+    /// In addition to the directional `MAXIMUM_OUTBOUND_PEERS` cap, this also enforces the
+    /// aggregate `MAXIMUM_NUMBER_OF_PEERS` budget; see [`should_accept_inbound`](Self::should_accept_inbound).
+    pub fn should_dial_outbound(&mut self, addr: &SocketAddr) -> bool {
+        if self.outbound.contains(addr)
+            || self.outbound.len() >= E::MAXIMUM_OUTBOUND_PEERS
+            || self.num_connected() >= E::MAXIMUM_NUMBER_OF_PEERS
+        {
+            return false;
+        }
+        !self.bans.is_banned(&addr.ip()) && self.access_control.is_connection_allowed(addr)
+    }
+
+    /// Records a connection failure for `addr`, banning it once it exceeds
+    /// `MAXIMUM_NUMBER_OF_FAILURES`. Returns `true` if `addr` is now banned.
+    pub fn record_failure(&mut self, addr: &SocketAddr) -> bool {
+        self.bans.record_failure(addr.ip())
+    }
+
+    /// Immediately bans `addr`, e.g. after it sends an invalid block or message.
+    pub fn ban(&mut self, addr: &SocketAddr) {
+        self.bans.ban(addr.ip());
+        self.remove(addr);
+    }
+
+    /// Returns `true` if `addr` is currently banned.
+    pub fn is_banned(&mut self, addr: &SocketAddr) -> bool {
+        self.bans.is_banned(&addr.ip())
+    }
+
+    /// Clears any ban recorded against `addr`, allowing operators to manage abusive peers
+    /// explicitly rather than waiting out the ban window.
+    pub fn clear_ban(&mut self, addr: &SocketAddr) {
+        self.bans.unban(&addr.ip());
+    }
+
+    /// Returns the set of currently banned peer IPs, along with how long each has been banned.
+    pub fn banned_peers(&self) -> impl Iterator<Item = (&std::net::IpAddr, Duration)> {
+        self.bans.banned_peers()
+    }
+
+    /// Returns `true` if the node still needs more outbound connections to reach
+    /// `PREFERRED_OUTBOUND_PEERS`, in which case preferred peers should continue to be re-dialed.
+    pub fn needs_more_outbound(&self) -> bool {
+        self.outbound.len() < E::PREFERRED_OUTBOUND_PEERS
+    }
+
+    /// Records that an inbound connection with `addr` was accepted.
+    pub fn record_inbound(&mut self, addr: SocketAddr) {
+        self.inbound.insert(addr);
+    }
+
+    /// Records that an outbound connection with `addr` was established.
+    pub fn record_outbound(&mut self, addr: SocketAddr) {
+        self.outbound.insert(addr);
+    }
+
+    /// Removes `addr` from both the inbound and outbound connection sets upon disconnection.
+    pub fn remove(&mut self, addr: &SocketAddr) {
+        self.inbound.remove(addr);
+        self.outbound.remove(addr);
+    }
+
+    /// Returns the number of currently connected inbound peers.
+    pub fn num_inbound(&self) -> usize {
+        self.inbound.len()
+    }
+
+    /// Returns the number of currently connected outbound peers.
+    pub fn num_outbound(&self) -> usize {
+        self.outbound.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::NodeType;
+    use snarkvm::dpc::testnet2::Testnet2;
+
+    #[derive(Clone, Debug, Default)]
+    struct TestEnvironment;
+
+    #[rustfmt::skip]
+    impl Environment for TestEnvironment {
+        type Network = Testnet2;
+        const NODE_TYPE: NodeType = NodeType::Client;
+        const MINIMUM_NUMBER_OF_PEERS: usize = 2;
+        const MAXIMUM_INBOUND_PEERS: usize = 1;
+        const MAXIMUM_OUTBOUND_PEERS: usize = 1;
+        const PREFERRED_OUTBOUND_PEERS: usize = 1;
+        const MAXIMUM_PENDING_PEERS: usize = 2;
+    }
+
+    #[test]
+    fn inbound_and_outbound_caps_are_independent() {
+        let mut manager = ConnectionManager::<TestEnvironment>::new(PeerAccessControl::default());
+        let inbound_addr: SocketAddr = "127.0.0.1:4130".parse().unwrap();
+        let outbound_addr: SocketAddr = "127.0.0.1:4131".parse().unwrap();
+
+        assert!(manager.should_accept_inbound(&inbound_addr));
+        manager.record_inbound(inbound_addr);
+        assert!(!manager.should_accept_inbound(&"127.0.0.1:4132".parse().unwrap()));
+
+        assert!(manager.should_dial_outbound(&outbound_addr));
+        manager.record_outbound(outbound_addr);
+        assert!(!manager.needs_more_outbound());
+    }
+
+    #[test]
+    fn pending_handshakes_are_capped_by_dropping_the_oldest() {
+        let mut manager = ConnectionManager::<TestEnvironment>::new(PeerAccessControl::default());
+
+        assert!(manager.begin_handshake("127.0.0.1:1".parse().unwrap()));
+        assert!(manager.begin_handshake("127.0.0.1:2".parse().unwrap()));
+        assert_eq!(manager.num_pending(), 2);
+
+        assert!(manager.begin_handshake("127.0.0.1:3".parse().unwrap()));
+        assert_eq!(manager.num_pending(), 2);
+
+        // The oldest handshake (port 1) should have been evicted to make room for port 3.
+        manager.complete_handshake(&"127.0.0.1:2".parse().unwrap());
+        assert_eq!(manager.num_pending(), 1);
+        manager.complete_handshake(&"127.0.0.1:3".parse().unwrap());
+        assert_eq!(manager.num_pending(), 0);
+    }
+}
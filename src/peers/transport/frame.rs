@@ -0,0 +1,116 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use aes::{
+    cipher::{NewCipher, StreamCipher},
+    Aes256,
+};
+use ctr::Ctr128BE;
+use hmac::{Hmac, Mac, NewMac};
+use sha2::{Digest, Sha256};
+
+type Aes256Ctr = Ctr128BE<Aes256>;
+type HmacSha256 = Hmac<Sha256>;
+
+/// The symmetric keys derived from a connection's handshake: one to encrypt frame bodies, and
+/// a separate one to authenticate them, following the encrypt-then-MAC construction so that a
+/// tampered ciphertext is rejected before it is ever decrypted.
+#[derive(Clone)]
+pub struct TransportKeys {
+    encryption_key: [u8; 32],
+    mac_key: [u8; 32],
+}
+
+impl TransportKeys {
+    /// Derives an encryption key and a separate MAC key from a 32-byte handshake `shared_secret`,
+    /// using domain-separated SHA-256 so the two keys are independent even though they share an
+    /// input.
+    pub fn derive(shared_secret: &[u8]) -> Self {
+        let mut encryption_key = [0u8; 32];
+        let mut mac_key = [0u8; 32];
+
+        encryption_key.copy_from_slice(&Sha256::digest([shared_secret, b"snarkos-transport-encryption"].concat()));
+        mac_key.copy_from_slice(&Sha256::digest([shared_secret, b"snarkos-transport-mac"].concat()));
+
+        Self { encryption_key, mac_key }
+    }
+}
+
+/// An encrypted, authenticated frame as it appears on the wire: a per-frame nonce, the
+/// encrypted body, and a MAC over the nonce and ciphertext.
+pub struct EncryptedFrame {
+    pub nonce: u128,
+    pub ciphertext: Vec<u8>,
+    pub mac: Vec<u8>,
+}
+
+/// The set of ways decrypting and verifying an [`EncryptedFrame`] can fail.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FrameError {
+    /// The frame's MAC did not match, indicating tampering or a key mismatch; the ciphertext
+    /// is not decrypted in this case.
+    MacMismatch,
+}
+
+/// Encrypts `plaintext` under `keys` using AES-256-CTR, tagged with an HMAC-SHA256 MAC computed
+/// over the nonce and ciphertext (encrypt-then-MAC). `nonce` must never repeat for a given key.
+pub fn encrypt_frame(keys: &TransportKeys, nonce: u128, plaintext: &[u8]) -> EncryptedFrame {
+    let mut ciphertext = plaintext.to_vec();
+    let mut cipher = Aes256Ctr::new(&keys.encryption_key.into(), &nonce.to_be_bytes().into());
+    cipher.apply_keystream(&mut ciphertext);
+
+    let mut mac = HmacSha256::new_from_slice(&keys.mac_key).expect("HMAC accepts keys of any length");
+    mac.update(&nonce.to_be_bytes());
+    mac.update(&ciphertext);
+
+    EncryptedFrame { nonce, ciphertext, mac: mac.finalize().into_bytes().to_vec() }
+}
+
+/// Verifies `frame`'s MAC and, only if it matches, decrypts and returns its plaintext.
+pub fn decrypt_frame(keys: &TransportKeys, frame: &EncryptedFrame) -> Result<Vec<u8>, FrameError> {
+    let mut mac = HmacSha256::new_from_slice(&keys.mac_key).expect("HMAC accepts keys of any length");
+    mac.update(&frame.nonce.to_be_bytes());
+    mac.update(&frame.ciphertext);
+    mac.verify(&frame.mac).map_err(|_| FrameError::MacMismatch)?;
+
+    let mut plaintext = frame.ciphertext.clone();
+    let mut cipher = Aes256Ctr::new(&keys.encryption_key.into(), &frame.nonce.to_be_bytes().into());
+    cipher.apply_keystream(&mut plaintext);
+
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_a_frame() {
+        let keys = TransportKeys::derive(b"a shared secret derived from the handshake");
+        let frame = encrypt_frame(&keys, 1, b"hello, peer");
+
+        assert_eq!(decrypt_frame(&keys, &frame).unwrap(), b"hello, peer");
+    }
+
+    #[test]
+    fn rejects_a_tampered_frame() {
+        let keys = TransportKeys::derive(b"a shared secret derived from the handshake");
+        let mut frame = encrypt_frame(&keys, 1, b"hello, peer");
+        frame.ciphertext[0] ^= 0xff;
+
+        assert_eq!(decrypt_frame(&keys, &frame), Err(FrameError::MacMismatch));
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::peers::transport::frame::TransportKeys;
+
+use snarkos_algorithms::encryption::GroupEncryption;
+use snarkos_curves::edwards_bls12::EdwardsProjective;
+use snarkos_models::{
+    algorithms::EncryptionScheme,
+    curves::{Group, ProjectiveCurve},
+};
+use snarkos_utilities::bytes::ToBytes;
+
+use rand::{rngs::OsRng, rngs::StdRng, Rng, SeedableRng};
+use sha2::{Digest, Sha256};
+
+type Handshake = GroupEncryption<EdwardsProjective>;
+
+/// The number of group elements exchanged as key material during the handshake.
+const SECRET_LEN: usize = 8;
+
+/// A fixed seed used to derive the `GroupEncryption` parameters shared by every peer on the
+/// network. These parameters - the curve generator and related public setup - must be identical
+/// on both ends of a connection, since `GroupEncryption` only decrypts correctly under the
+/// parameters it was encrypted under; only the per-connection keypair is ephemeral.
+const TRANSPORT_SCHEME_SEED: u64 = 0x736e61726b4f53; // "snarkOS" as bytes, read as a u64.
+
+/// Returns the `GroupEncryption` parameters shared by every peer on the network.
+fn shared_transport_scheme() -> Handshake {
+    Handshake::setup(&mut StdRng::seed_from_u64(TRANSPORT_SCHEME_SEED))
+}
+
+/// Serializes a group element to its canonical little-endian byte representation, which - unlike
+/// `Debug` output - is a stable encoding suitable for key derivation.
+fn canonical_bytes(element: &EdwardsProjective) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    element.write_le(&mut bytes).expect("group element serialization is infallible");
+    bytes
+}
+
+/// An ephemeral keypair generated fresh for a single connection, so that compromising one
+/// session's keys does not expose the plaintext of any other session.
+///
+/// This keypair is not bound to either peer's static node identity: nothing here proves that
+/// `public_key` was not substituted in transit, so the exchange defends against a passive
+/// eavesdropper but not an active on-path attacker at handshake time.
+pub struct EphemeralKeyPair {
+    scheme: Handshake,
+    private_key: <Handshake as EncryptionScheme>::PrivateKey,
+    /// The public key to send to the remote peer as the first handshake message.
+    pub public_key: <Handshake as EncryptionScheme>::PublicKey,
+}
+
+impl EphemeralKeyPair {
+    /// Generates a fresh ephemeral keypair for a new connection, under the parameters shared by
+    /// every peer on the network.
+    pub fn generate() -> Self {
+        let scheme = shared_transport_scheme();
+        let (private_key, public_key) = scheme.keygen(&mut OsRng);
+
+        Self { scheme, private_key, public_key }
+    }
+
+    /// Encrypts a freshly-sampled secret to the remote peer's ephemeral `public_key`, returning
+    /// the ciphertext to send as well as the plaintext secret contributed by this side of the
+    /// handshake.
+    pub fn seal_secret(
+        &self,
+        remote_public_key: &<Handshake as EncryptionScheme>::PublicKey,
+    ) -> (Vec<EdwardsProjective>, Vec<EdwardsProjective>) {
+        let rng = &mut OsRng;
+        let secret = (0..SECRET_LEN).map(|_| EdwardsProjective::rand(rng)).collect::<Vec<_>>();
+        let ciphertext = self
+            .scheme
+            .encrypt(remote_public_key, &secret, rng)
+            .expect("encryption of handshake secret failed");
+
+        (ciphertext, secret)
+    }
+
+    /// Decrypts a ciphertext produced by [`seal_secret`](Self::seal_secret) on the remote side.
+    pub fn open_secret(&self, ciphertext: &[EdwardsProjective]) -> Vec<EdwardsProjective> {
+        self.scheme.decrypt(&self.private_key, ciphertext).expect("decryption of handshake secret failed")
+    }
+
+    /// Completes the handshake by mixing the secret this side contributed with the secret
+    /// opened from the remote side, deriving the pair of symmetric keys used to encrypt and
+    /// authenticate every subsequent frame on the connection.
+    ///
+    /// The two contributions are combined by XOR-ing their canonical byte encodings element by
+    /// element rather than concatenating them in `local`-then-`remote` order, so that both ends -
+    /// which each call this with the *other* side's secret as `remote_secret` - derive the same
+    /// `TransportKeys` regardless of which side is "local".
+    pub fn derive_transport_keys(
+        local_secret: &[EdwardsProjective],
+        remote_secret: &[EdwardsProjective],
+    ) -> TransportKeys {
+        assert_eq!(local_secret.len(), remote_secret.len(), "handshake secrets must be the same length");
+
+        let mut combined = Vec::new();
+        for (local, remote) in local_secret.iter().zip(remote_secret.iter()) {
+            let local_bytes = canonical_bytes(local);
+            let remote_bytes = canonical_bytes(remote);
+            assert_eq!(local_bytes.len(), remote_bytes.len(), "group elements must serialize to a fixed width");
+
+            combined.extend(local_bytes.iter().zip(remote_bytes.iter()).map(|(l, r)| l ^ r));
+        }
+
+        let shared_secret = Sha256::digest(&combined);
+
+        TransportKeys::derive(&shared_secret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peers::transport::frame::{decrypt_frame, encrypt_frame};
+
+    #[test]
+    fn both_sides_of_the_handshake_derive_equal_transport_keys() {
+        let initiator = EphemeralKeyPair::generate();
+        let responder = EphemeralKeyPair::generate();
+
+        // Each side seals a fresh secret to the other's public key, and opens the ciphertext
+        // it receives in return.
+        let (ciphertext_for_responder, initiator_secret) = initiator.seal_secret(&responder.public_key);
+        let (ciphertext_for_initiator, responder_secret) = responder.seal_secret(&initiator.public_key);
+
+        let responder_opened_secret = responder.open_secret(&ciphertext_for_responder);
+        let initiator_opened_secret = initiator.open_secret(&ciphertext_for_initiator);
+
+        assert_eq!(responder_opened_secret, initiator_secret);
+        assert_eq!(initiator_opened_secret, responder_secret);
+
+        let initiator_keys = EphemeralKeyPair::derive_transport_keys(&initiator_secret, &responder_opened_secret);
+        let responder_keys = EphemeralKeyPair::derive_transport_keys(&responder_secret, &initiator_opened_secret);
+
+        let frame = encrypt_frame(&initiator_keys, 0, b"hello from the initiator");
+        assert_eq!(decrypt_frame(&responder_keys, &frame).unwrap(), b"hello from the initiator");
+    }
+}
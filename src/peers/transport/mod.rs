@@ -0,0 +1,32 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+//! An RLPx-style encrypted transport for peer connections, built on the
+//! [`GroupEncryption`](snarkos_algorithms::encryption::GroupEncryption) scheme for the
+//! ephemeral key exchange and AES-256-CTR with an HMAC-SHA256 MAC for per-frame
+//! encrypt-then-MAC framing. The functions here do not read `Environment::ENCRYPTED_TRANSPORT`.
+//!
+//! The handshake is unauthenticated: it is not bound to either peer's static node identity, so
+//! it defends against a passive eavesdropper but not against an active attacker who can
+//! substitute ephemeral keys in transit during connection setup. Once a connection is
+//! established, every frame is still individually MAC-verified, so on-path tampering with data
+//! frames is detected and rejected.
+
+pub mod frame;
+pub use frame::*;
+
+pub mod handshake;
+pub use handshake::*;
@@ -0,0 +1,101 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    environment::Environment,
+    sync::{import_queue::spawn_import_queue, BlockImporter, ImportQueueHandle, ImportResult, SyncStatusHandle},
+};
+
+use snarkvm::dpc::Block;
+
+use tokio::sync::mpsc;
+
+/// A handle used by the network message loop and RPC to interact with the syncing engine
+/// without sharing its internal state directly.
+#[derive(Clone, Debug)]
+pub struct SyncingEngineHandle<E: Environment> {
+    status: SyncStatusHandle,
+    import_queue: ImportQueueHandle<E::Network>,
+}
+
+impl<E: Environment> SyncingEngineHandle<E> {
+    /// Returns a handle for querying the engine's current sync height and target.
+    pub fn status(&self) -> SyncStatusHandle {
+        self.status.clone()
+    }
+
+    /// Hands a fetched block to the decoupled import queue for asynchronous verification.
+    pub async fn submit_block(&self, block: Block<E::Network>) -> Result<(), mpsc::error::SendError<Block<E::Network>>> {
+        self.import_queue.submit(block).await
+    }
+
+    /// Records the syncing target reported by a peer, e.g. from its block height in a `Ping`.
+    pub fn set_target_height(&self, target_height: u32) {
+        self.status.set_target_height(target_height);
+    }
+}
+
+/// The standalone syncing engine task. It owns the node's sync state and the import queue,
+/// decoupling block verification from the network message loop so that a slow importer cannot
+/// stall message processing.
+struct SyncingEngine<E: Environment> {
+    status: SyncStatusHandle,
+    import_results: mpsc::Receiver<ImportResult>,
+    _environment: std::marker::PhantomData<E>,
+}
+
+impl<E: Environment> SyncingEngine<E> {
+    /// Spawns the syncing engine and its import queue, returning a handle to interact with both.
+    pub fn spawn(importer: impl BlockImporter<E::Network>) -> SyncingEngineHandle<E> {
+        let (import_queue, import_results) = spawn_import_queue::<E>(importer);
+        let status = SyncStatusHandle::default();
+
+        let engine = Self { status: status.clone(), import_results, _environment: std::marker::PhantomData };
+        tokio::spawn(engine.run());
+
+        SyncingEngineHandle { status, import_queue }
+    }
+
+    /// Applies import results to the engine's sync status as they arrive, until the import
+    /// queue shuts down.
+    ///
+    /// With `NUMBER_OF_SYNC_THREADS > 1`, results can arrive out of height order - e.g. a worker
+    /// importing block `N` may finish after another worker has already reported block `N + 1` -
+    /// so the height is only ever advanced, never regressed.
+    async fn run(mut self) {
+        while let Some(result) = self.import_results.recv().await {
+            match result {
+                ImportResult::Imported { height } => {
+                    let current_height = self.status.sync_status().current_height;
+                    self.status.set_current_height(current_height.max(height));
+                }
+                ImportResult::Rejected { height, reason } => {
+                    // The import queue doesn't carry peer identity, so this can't ban the
+                    // offending peer directly; it surfaces the rejection to whoever can, via
+                    // the log and the status handle's rejection count.
+                    log::warn!("Block {} was rejected during import: {}", height, reason);
+                    self.status.record_rejected_block();
+                }
+            }
+        }
+    }
+}
+
+/// Spawns the syncing engine for `E`, backed by `importer`, and returns a handle for the rest
+/// of the node to interact with it.
+pub fn spawn_syncing_engine<E: Environment>(importer: impl BlockImporter<E::Network>) -> SyncingEngineHandle<E> {
+    SyncingEngine::<E>::spawn(importer)
+}
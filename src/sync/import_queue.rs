@@ -0,0 +1,95 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::environment::Environment;
+
+use snarkvm::dpc::{Block, Network};
+
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+
+/// The outcome of attempting to verify and apply a single fetched block.
+#[derive(Clone, Debug)]
+pub enum ImportResult {
+    /// The block at `height` was verified and applied to the ledger.
+    Imported { height: u32 },
+    /// The block at `height` failed verification and was discarded, along with the reason.
+    Rejected { height: u32, reason: String },
+}
+
+/// Verifies and applies a single block to the ledger. Implemented by whatever subsystem owns
+/// ledger state; the import queue only drives calls to it off of the network message loop.
+pub trait BlockImporter<N: Network>: Send + Sync + 'static {
+    /// Verifies and applies `block`, returning an error describing why if it is invalid.
+    fn import(&self, block: Block<N>) -> Result<(), String>;
+}
+
+/// A handle used to submit fetched blocks to the import queue task without waiting for them
+/// to be verified and applied.
+#[derive(Clone, Debug)]
+pub struct ImportQueueHandle<N: Network> {
+    blocks: mpsc::Sender<Block<N>>,
+}
+
+impl<N: Network> ImportQueueHandle<N> {
+    /// Submits `block` for asynchronous verification and import, backpressuring the caller
+    /// once the queue is full rather than allowing unbounded growth.
+    pub async fn submit(&self, block: Block<N>) -> Result<(), mpsc::error::SendError<Block<N>>> {
+        self.blocks.send(block).await
+    }
+}
+
+/// Spawns a standalone import queue backed by `NUMBER_OF_SYNC_THREADS` worker tasks that verify
+/// and apply blocks handed to them via the returned [`ImportQueueHandle`], reporting each
+/// outcome back over the returned channel. This decouples block verification - which may be
+/// slow - from the network message loop that fetches blocks, and spreads `MAXIMUM_BLOCK_REQUEST`
+/// batches across the configured worker pool so a slow importer cannot stall message processing.
+pub fn spawn_import_queue<E: Environment>(
+    importer: impl BlockImporter<E::Network>,
+) -> (ImportQueueHandle<E::Network>, mpsc::Receiver<ImportResult>) {
+    let (block_sender, block_receiver) = mpsc::channel::<Block<E::Network>>(E::MAXIMUM_BLOCK_REQUEST as usize);
+    let (result_sender, result_receiver) = mpsc::channel(E::MAXIMUM_BLOCK_REQUEST as usize);
+
+    let block_receiver = Arc::new(Mutex::new(block_receiver));
+    let importer = Arc::new(importer);
+
+    for _ in 0..E::NUMBER_OF_SYNC_THREADS {
+        let block_receiver = block_receiver.clone();
+        let importer = importer.clone();
+        let result_sender = result_sender.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let block = match block_receiver.lock().await.recv().await {
+                    Some(block) => block,
+                    None => break,
+                };
+
+                let height = block.height();
+                let result = match importer.import(block) {
+                    Ok(()) => ImportResult::Imported { height },
+                    Err(reason) => ImportResult::Rejected { height, reason },
+                };
+
+                if result_sender.send(result).await.is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    (ImportQueueHandle { blocks: block_sender }, result_receiver)
+}